@@ -6,10 +6,18 @@ use lambda_runtime::{Error as LambdaError, LambdaEvent};
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::Value;
 
-pub use aws_lambda_events::{s3::S3Event, sns::SnsEvent, sqs::SqsEvent};
+pub use aws_lambda_events::{
+    apigw::{ApiGatewayCustomAuthorizerRequest, ApiGatewayProxyRequest, ApiGatewayV2httpRequest},
+    dynamodb::Event as DynamoDbEvent,
+    eventbridge::EventBridgeEvent,
+    kinesis::KinesisEvent,
+    s3::S3Event,
+    sns::SnsEvent,
+    sqs::SqsEvent,
+};
 
 /// Trait defining methods that AWS events must implement.
-pub trait AwsEvent: Send + Sync + Sized + DeserializeOwned {
+pub trait AwsEvent: Send + Sync + Sized + Clone + DeserializeOwned + Serialize {
     /// Deserializes an AWS event from a JSON request.
     fn from_request(request: &Value) -> Result<Self, serde_json::Error> {
         serde_json::from_value(request.clone())
@@ -26,6 +34,44 @@ pub trait AwsEvent: Send + Sync + Sized + DeserializeOwned {
 
     /// Returns the event name for the AWS event.
     fn event_name(&self) -> String;
+
+    /// Splits a batch event into one event per record, so each record can be
+    /// routed and handled independently of the others in its batch.
+    ///
+    /// Defaults to returning the event unchanged, which is correct for event
+    /// types that never carry more than one record.
+    fn split_records(&self) -> Vec<Self> {
+        vec![self.clone()]
+    }
+
+    /// Returns the value that `route_matching` patterns are evaluated
+    /// against. Defaults to `event_name()`; override when a different facet
+    /// of the event, such as an S3 object key, is the more natural thing to
+    /// match a pattern against.
+    fn match_key(&self) -> Option<String> {
+        Some(self.event_name())
+    }
+
+    /// For batch sources that support AWS's partial batch failure redrive
+    /// contract: the identifier AWS expects back in `batchItemFailures` for
+    /// each record, in the same order as `split_records`. Defaults to
+    /// `None` for event types that don't support partial batch failures.
+    fn batch_item_ids(&self) -> Option<Vec<String>> {
+        None
+    }
+
+    /// Whether this source delivers records from a single ordered cursor
+    /// (DynamoDB Streams, Kinesis) rather than independent messages (SQS).
+    ///
+    /// Ordered sources must have their records dispatched one at a time, in
+    /// order, and `ReportBatchItemFailures` only supports reporting the
+    /// *first* failed record's id - everything after it is redriven
+    /// regardless of whether it would have succeeded on its own. Defaults to
+    /// `false`; only meaningful when [`AwsEvent::batch_item_ids`] also
+    /// returns `Some`.
+    fn ordered_batch(&self) -> bool {
+        false
+    }
 }
 
 // Implement `AwsEvent` trait for `S3Event`.
@@ -33,6 +79,24 @@ impl AwsEvent for S3Event {
     fn event_name(&self) -> String {
         self.records[0].event_name.clone().expect("No event name!")
     }
+
+    fn split_records(&self) -> Vec<Self> {
+        self.records
+            .iter()
+            .map(|record| S3Event {
+                records: vec![record.clone()],
+            })
+            .collect()
+    }
+
+    fn match_key(&self) -> Option<String> {
+        let key = self.records[0].s3.object.key.as_ref()?;
+        Some(
+            urlencoding::decode(key)
+                .map(|decoded| decoded.into_owned())
+                .unwrap_or_else(|_| key.clone()),
+        )
+    }
 }
 
 // Implement `AwsEvent` trait for `SnsEvent`.
@@ -40,6 +104,15 @@ impl AwsEvent for SnsEvent {
     fn event_name(&self) -> String {
         self.records[0].sns.topic_arn.clone()
     }
+
+    fn split_records(&self) -> Vec<Self> {
+        self.records
+            .iter()
+            .map(|record| SnsEvent {
+                records: vec![record.clone()],
+            })
+            .collect()
+    }
 }
 
 // Implement `AwsEvent` trait for `SqsEvent`.
@@ -50,6 +123,126 @@ impl AwsEvent for SqsEvent {
             .clone()
             .expect("No topic ARN!")
     }
+
+    fn split_records(&self) -> Vec<Self> {
+        self.records
+            .iter()
+            .map(|record| SqsEvent {
+                records: vec![record.clone()],
+            })
+            .collect()
+    }
+
+    fn batch_item_ids(&self) -> Option<Vec<String>> {
+        Some(
+            self.records
+                .iter()
+                .map(|record| record.message_id.clone().unwrap_or_default())
+                .collect(),
+        )
+    }
+}
+
+// Implement `AwsEvent` trait for `ApiGatewayProxyRequest` (REST API).
+impl AwsEvent for ApiGatewayProxyRequest {
+    fn event_name(&self) -> String {
+        format!(
+            "{} {}",
+            self.http_method,
+            self.resource.clone().unwrap_or_default()
+        )
+    }
+}
+
+// Implement `AwsEvent` trait for `ApiGatewayV2httpRequest` (HTTP API).
+impl AwsEvent for ApiGatewayV2httpRequest {
+    fn event_name(&self) -> String {
+        self.route_key.clone().unwrap_or_else(|| {
+            format!(
+                "{} {}",
+                self.request_context.http.method,
+                self.request_context.http.path.clone().unwrap_or_default()
+            )
+        })
+    }
+}
+
+// Implement `AwsEvent` trait for `ApiGatewayCustomAuthorizerRequest`.
+impl AwsEvent for ApiGatewayCustomAuthorizerRequest {
+    fn event_name(&self) -> String {
+        self.method_arn.clone().unwrap_or_default()
+    }
+}
+
+// Implement `AwsEvent` trait for `EventBridgeEvent`.
+impl AwsEvent for EventBridgeEvent {
+    fn event_name(&self) -> String {
+        format!("{} {}", self.detail_type, self.source)
+    }
+}
+
+// Implement `AwsEvent` trait for `DynamoDbEvent`.
+impl AwsEvent for DynamoDbEvent {
+    fn event_name(&self) -> String {
+        self.records[0]
+            .event_source_arn
+            .clone()
+            .expect("No event source ARN!")
+    }
+
+    fn split_records(&self) -> Vec<Self> {
+        self.records
+            .iter()
+            .map(|record| DynamoDbEvent {
+                records: vec![record.clone()],
+            })
+            .collect()
+    }
+
+    fn batch_item_ids(&self) -> Option<Vec<String>> {
+        Some(
+            self.records
+                .iter()
+                .map(|record| record.change.sequence_number.clone().unwrap_or_default())
+                .collect(),
+        )
+    }
+
+    fn ordered_batch(&self) -> bool {
+        true
+    }
+}
+
+// Implement `AwsEvent` trait for `KinesisEvent`.
+impl AwsEvent for KinesisEvent {
+    fn event_name(&self) -> String {
+        self.records[0]
+            .event_source_arn
+            .clone()
+            .expect("No event source ARN!")
+    }
+
+    fn split_records(&self) -> Vec<Self> {
+        self.records
+            .iter()
+            .map(|record| KinesisEvent {
+                records: vec![record.clone()],
+            })
+            .collect()
+    }
+
+    fn batch_item_ids(&self) -> Option<Vec<String>> {
+        Some(
+            self.records
+                .iter()
+                .map(|record| record.kinesis.sequence_number.clone().unwrap_or_default())
+                .collect(),
+        )
+    }
+
+    fn ordered_batch(&self) -> bool {
+        true
+    }
 }
 
 /// Type alias for a Lambda future, wrapping a boxed dynamic Future trait.
@@ -68,14 +261,14 @@ pub trait Callable<R> {
 pub struct AwsEventHandler<T, R>
 where
     T: AwsEvent,
-    R: Debug + Serialize,
+    R: Debug + Serialize + Send,
 {
     event_type: PhantomData<T>, // Marker for the event type.
     handler: Box<dyn Fn(LambdaEvent<T>) -> LambdaFuture<R> + Send + Sync>, // Actual event handler function.
 }
 
 // Implementation for `AwsEventHandler`.
-impl<T: AwsEvent, R: Debug + Serialize> AwsEventHandler<T, R> {
+impl<T: AwsEvent, R: Debug + Serialize + Send> AwsEventHandler<T, R> {
     /// Constructs a new `AwsEventHandler`.
     pub fn new(handler: Box<dyn Fn(LambdaEvent<T>) -> LambdaFuture<R> + Send + Sync>) -> Self {
         AwsEventHandler {
@@ -86,7 +279,7 @@ impl<T: AwsEvent, R: Debug + Serialize> AwsEventHandler<T, R> {
 }
 
 // Implement `Callable` trait for `AwsEventHandler`.
-impl<T: AwsEvent, R: Debug + Serialize> Callable<R> for AwsEventHandler<T, R> {
+impl<T: AwsEvent, R: Debug + Serialize + Send> Callable<R> for AwsEventHandler<T, R> {
     /// Calls the event handler for an AWS Lambda event.
     fn call(&self, event: LambdaEvent<Value>) -> LambdaFuture<R> {
         // Convert the generic Request into a specific event type.
@@ -1,63 +1,500 @@
 use std::any::TypeId;
-use std::collections::HashMap;
-use std::error::Error;
-use std::fmt::Debug;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Debug};
 use std::future::Future;
-use std::task::{Context, Poll};
+use std::sync::Arc;
+use std::task::{Context as TaskContext, Poll};
 
-use lambda_runtime::{Error as LambdaError, LambdaEvent};
-use serde::Serialize;
+use futures::future::join_all;
+use lambda_runtime::{Context, Error as LambdaError, LambdaEvent};
+use regex::Regex;
+use serde::{Serialize, Serializer};
 use serde_json::Value;
-use tower::Service;
+use tokio::sync::Mutex;
+use tower::util::BoxService;
+use tower::{Layer, Service, ServiceExt};
 
-use crate::events::{
-    AwsEvent, AwsEventHandler, Callable, LambdaFuture, S3Event, SnsEvent, SqsEvent,
-};
+use crate::events::{AwsEvent, AwsEventHandler, Callable, LambdaFuture};
+use crate::response::{FunctionResponse, IntoFunctionResponse};
 
-/// Parse the AWS Lambda event and return its type and name as a key.
-///
-/// # Arguments
-///
-/// * `event` - The AWS Lambda event to parse.
-///
-/// # Returns
-///
-/// A `Result` containing a tuple `(TypeId, String)` that represents
-/// the event's type and name, or an error if parsing fails.
-fn get_event_key(
-    event: LambdaEvent<Value>,
-) -> Result<(TypeId, String), Box<dyn Error + Send + Sync>> {
-    // Attempt to parse as S3 event
-    if let Ok(parsed_event) = S3Event::from_request(&event.payload) {
-        return Ok((TypeId::of::<S3Event>(), parsed_event.event_name()));
+/// Object-safe view of a parsed [`AwsEvent`], letting the router dispatch an
+/// event without knowing its concrete type ahead of time. Implemented for
+/// every `E: AwsEvent` so registering a route via `route::<E>` is enough to
+/// make the router able to recognise and dispatch `E`.
+trait DispatchableEvent<R>: Send
+where
+    R: Debug + Serialize + Send,
+{
+    /// Splits the event into its records and routes each through `core`.
+    fn dispatch(
+        self: Box<Self>,
+        core: &RouterCore<R>,
+        context: &Context,
+    ) -> Vec<LambdaFuture<FunctionResponse<R>>>;
+
+    /// The per-record identifiers AWS expects back for a partial batch
+    /// failure response, if this event type supports one.
+    fn batch_item_ids(&self) -> Option<Vec<String>>;
+
+    /// Whether this event's records must be dispatched one at a time, in
+    /// order, rather than concurrently. See [`AwsEvent::ordered_batch`].
+    fn ordered_batch(&self) -> bool;
+}
+
+impl<E, R> DispatchableEvent<R> for E
+where
+    E: AwsEvent + 'static,
+    R: Debug + Serialize + Send,
+{
+    fn dispatch(
+        self: Box<Self>,
+        core: &RouterCore<R>,
+        context: &Context,
+    ) -> Vec<LambdaFuture<FunctionResponse<R>>> {
+        dispatch_records(core, context, self.split_records())
+    }
+
+    fn batch_item_ids(&self) -> Option<Vec<String>> {
+        AwsEvent::batch_item_ids(self)
+    }
+
+    fn ordered_batch(&self) -> bool {
+        AwsEvent::ordered_batch(self)
+    }
+}
+
+/// A probe that attempts to parse the raw Lambda payload as a single
+/// concrete `AwsEvent` type, registered once per type the router has routes
+/// for. Dispatch tries each probe in registration order and dispatches
+/// through the first one that recognises the payload, so adding a new
+/// `AwsEvent` impl never requires touching the router itself.
+type EventProbe<R> = Box<dyn Fn(&Value) -> Option<Box<dyn DispatchableEvent<R>>> + Send + Sync>;
+
+fn probe_for<E, R>() -> EventProbe<R>
+where
+    E: AwsEvent + 'static,
+    R: Debug + Serialize + Send,
+{
+    Box::new(|payload: &Value| {
+        E::from_request(payload)
+            .ok()
+            .map(|event| Box::new(event) as Box<dyn DispatchableEvent<R>>)
+    })
+}
+
+/// Identifier of a batch record that a handler failed to process (an SQS
+/// message id, or a DynamoDB/Kinesis sequence number), matching the shape
+/// AWS Lambda's poller expects for partial batch redrive.
+#[derive(Debug, Serialize)]
+pub struct BatchItemFailure {
+    #[serde(rename = "itemIdentifier")]
+    pub item_identifier: String,
+}
+
+/// The `ReportBatchItemFailures` response shape: only the messages that
+/// failed are listed, so the poller redrives just those.
+#[derive(Debug, Serialize)]
+pub struct SqsBatchResponse {
+    #[serde(rename = "batchItemFailures")]
+    pub batch_item_failures: Vec<BatchItemFailure>,
+}
+
+/// Response returned by [`LambdaHandler`] once every record in a batch has
+/// been routed. Serializes as a plain `R` when every record succeeded, and
+/// as [`SqsBatchResponse`] when an event type with partial batch failure
+/// support (see [`AwsEvent::batch_item_ids`]) turned up failures, so the
+/// poller only redrives the records that actually failed.
+#[derive(Debug)]
+pub enum BatchResponse<R> {
+    Single(R),
+    PartialFailures(SqsBatchResponse),
+}
+
+impl<R: Serialize> Serialize for BatchResponse<R> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            BatchResponse::Single(response) => response.serialize(serializer),
+            BatchResponse::PartialFailures(failures) => failures.serialize(serializer),
+        }
+    }
+}
+
+/// Routes each record of a batch event through `core`, independently,
+/// returning one future per record in its original order.
+fn dispatch_records<E, R>(
+    core: &RouterCore<R>,
+    context: &Context,
+    records: Vec<E>,
+) -> Vec<LambdaFuture<FunctionResponse<R>>>
+where
+    E: AwsEvent + 'static,
+    R: Debug + Serialize + Send,
+{
+    records
+        .into_iter()
+        .map(|record| {
+            let handler = core.resolve(&record);
+            let payload =
+                serde_json::to_value(&record).expect("failed to re-serialize AWS event record");
+            let sub_event = LambdaEvent {
+                payload,
+                context: context.clone(),
+            };
+            match handler {
+                Some(handler) => handler.call(sub_event),
+                None => match &core.fallback {
+                    Some(fallback) => fallback(sub_event),
+                    None => Box::pin(async { Err(LambdaError::from(NoHandlerError)) }),
+                },
+            }
+        })
+        .collect()
+}
+
+/// Reduces a batch of per-record results to a single response: the last
+/// successful one, or the first error across any record.
+async fn reduce_batch<R>(futures: Vec<LambdaFuture<R>>) -> Result<R, LambdaError> {
+    let mut last_ok = None;
+    for result in join_all(futures).await {
+        match result {
+            Ok(response) => last_ok = Some(response),
+            Err(err) => return Err(err),
+        }
+    }
+    last_ok.ok_or_else(|| LambdaError::from(NoHandlerError))
+}
+
+/// Marker stored inside a [`LambdaError`] when a record's event was parsed
+/// successfully but no route matched its name or pattern, and no
+/// [`LambdaHandler::fallback`] was registered to recover it. Lets
+/// [`RouterCore::call`] tell "no route found" apart from a handler's own
+/// error once both have been reduced to a single [`LambdaError`] by
+/// [`reduce_batch`].
+#[derive(Debug)]
+struct NoHandlerError;
+
+impl fmt::Display for NoHandlerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "I don't have a handler for this event!")
+    }
+}
+
+impl std::error::Error for NoHandlerError {}
+
+/// Why a [`LambdaHandler`] could not produce a response for an invocation,
+/// so callers - and downstream `tower` layers - can match on the cause
+/// instead of string-sniffing a generic [`LambdaError`].
+#[derive(Debug)]
+pub enum RouterError {
+    /// The payload didn't match any registered `AwsEvent` type.
+    ParseFailed,
+    /// The payload matched a registered `AwsEvent` type, but no
+    /// `route`/`route_matching` matched its name or pattern.
+    NoRouteFound,
+    /// A matched handler ran and returned an error.
+    HandlerError(LambdaError),
+}
+
+impl fmt::Display for RouterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RouterError::ParseFailed => {
+                write!(f, "unable to parse the event payload as any registered event type")
+            }
+            RouterError::NoRouteFound => write!(f, "no route matched the event"),
+            RouterError::HandlerError(err) => write!(f, "handler failed: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for RouterError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RouterError::HandlerError(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Converts a dispatch failure into a [`RouterError`], recognising
+/// [`NoHandlerError`] as [`RouterError::NoRouteFound`] rather than treating
+/// it as an opaque handler error.
+fn classify_dispatch_error(err: LambdaError) -> RouterError {
+    match err.downcast::<NoHandlerError>() {
+        Ok(_) => RouterError::NoRouteFound,
+        Err(err) => RouterError::HandlerError(err),
+    }
+}
+
+/// Type alias for the future returned by [`LambdaHandler`]'s and
+/// [`RouterCore`]'s `Service` impls, which resolve to a [`RouterError`]
+/// rather than the plain [`LambdaError`] an individual handler returns.
+type RouterFuture<R> = std::pin::Pin<Box<dyn Future<Output = Result<R, RouterError>> + Send>>;
+
+/// Adapts a boxed [`Callable`] so it can be wrapped by [`tower::Layer`]s via
+/// [`LambdaHandler::route_layered`].
+pub struct CallableService<R> {
+    callable: Box<dyn Callable<R>>,
+}
+
+impl<R> Service<LambdaEvent<Value>> for CallableService<R> {
+    type Response = R;
+    type Error = LambdaError;
+    type Future = LambdaFuture<R>;
+
+    fn poll_ready(&mut self, _: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: LambdaEvent<Value>) -> Self::Future {
+        self.callable.call(req)
+    }
+}
+
+/// Adapts a layered [`tower::Service`] back into a [`Callable`], so
+/// [`LambdaHandler::route_layered`] can store it in the same
+/// `handlers`/`pattern_handlers` tables as a plain route. The service is
+/// shared behind a mutex since `Callable::call` takes `&self`, while
+/// `Service::call` needs `&mut self`.
+struct LayeredCallable<S> {
+    inner: Arc<Mutex<S>>,
+}
+
+impl<S, R> Callable<R> for LayeredCallable<S>
+where
+    S: Service<LambdaEvent<Value>, Response = R, Error = LambdaError> + Send + 'static,
+    S::Future: Send + 'static,
+{
+    fn call(&self, event: LambdaEvent<Value>) -> LambdaFuture<R> {
+        let inner = Arc::clone(&self.inner);
+        Box::pin(async move {
+            let mut service = inner.lock().await;
+            service.ready().await?;
+            service.call(event).await
+        })
+    }
+}
+
+/// The router's handlers, pattern routes and event probes - everything
+/// needed to dispatch an event once it's been registered. Split out from
+/// [`LambdaHandler`] so it can be boxed up behind [`tower::Layer`]s applied
+/// via [`LambdaHandler::layer`] while still implementing [`Service`] itself.
+struct RouterCore<R>
+where
+    R: Debug + Serialize + Send + 'static,
+{
+    // Mapping of event type and name to its handler.
+    handlers: HashMap<(TypeId, String), Box<dyn Callable<FunctionResponse<R>> + Send>>,
+    // Pattern-matched routes, consulted in registration order when the
+    // exact-match `handlers` map misses.
+    pattern_handlers: Vec<(TypeId, Regex, Box<dyn Callable<FunctionResponse<R>> + Send>)>,
+    // One probe per `AwsEvent` type a route has been registered for, in
+    // registration order; see `EventProbe`.
+    probes: Vec<EventProbe<R>>,
+    // Tracks which event types already have a probe, so registering
+    // multiple routes for the same type doesn't duplicate probing work.
+    probed_types: HashSet<TypeId>,
+    // Catch-all invoked whenever no route matches - with the raw payload
+    // when no probe recognised the event, or with the unmatched record
+    // re-serialized as a one-record event when a parsed record's name or
+    // pattern had no registered handler. See `LambdaHandler::fallback`.
+    fallback: Option<Box<dyn Fn(LambdaEvent<Value>) -> LambdaFuture<FunctionResponse<R>> + Send + Sync>>,
+}
+
+impl<R: Debug + Serialize + Send> RouterCore<R> {
+    fn new() -> Self {
+        RouterCore {
+            handlers: HashMap::new(),
+            pattern_handlers: Vec::new(),
+            probes: Vec::new(),
+            probed_types: HashSet::new(),
+            fallback: None,
+        }
+    }
+
+    /// Registers a probe for `E`, unless one is already registered.
+    fn ensure_probed<E: AwsEvent + 'static>(&mut self) {
+        if self.probed_types.insert(TypeId::of::<E>()) {
+            self.probes.push(probe_for::<E, R>());
+        }
+    }
+
+    /// Finds the handler registered for a record: first by exact
+    /// `(TypeId, event_name)` match, falling back to the first
+    /// `route_matching` pattern, in registration order, whose regex matches
+    /// the record's `match_key`.
+    fn resolve<E: AwsEvent + 'static>(&self, record: &E) -> Option<&(dyn Callable<FunctionResponse<R>> + Send)> {
+        let key = (TypeId::of::<E>(), record.event_name());
+        if let Some(handler) = self.handlers.get(&key) {
+            return Some(handler.as_ref());
+        }
+
+        let type_id = TypeId::of::<E>();
+        let match_target = record.match_key()?;
+        self.pattern_handlers
+            .iter()
+            .find(|(t, regex, _)| *t == type_id && regex.is_match(&match_target))
+            .map(|(_, _, handler)| handler.as_ref())
+    }
+}
+
+impl<R: Debug + Serialize + Send> Service<LambdaEvent<Value>> for RouterCore<R> {
+    type Response = BatchResponse<FunctionResponse<R>>;
+    type Error = RouterError;
+    type Future = RouterFuture<BatchResponse<FunctionResponse<R>>>;
+
+    fn poll_ready(&mut self, _: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    /// Processes an incoming event, routing each of its records to the
+    /// appropriate handler and aggregating the results.
+    ///
+    /// For an event type whose [`AwsEvent::batch_item_ids`] returns `Some`, a
+    /// handler failure for one record does not fail the others: the
+    /// invocation as a whole succeeds and the response reports the failed
+    /// record id(s) via [`BatchResponse::PartialFailures`], so the poller
+    /// redrives just the records that still need it. Unordered sources
+    /// (`SqsEvent`) dispatch every record concurrently and report one entry
+    /// per failed record. Ordered sources (`DynamoDbEvent`, `KinesisEvent`,
+    /// see [`AwsEvent::ordered_batch`]) dispatch records one at a time, in
+    /// order, and stop at the first failure, reporting only that record's
+    /// id - matching `ReportBatchItemFailures`'s single-cursor contract for
+    /// these sources, where everything after the reported id is redriven
+    /// regardless of whether it would have succeeded on its own.
+    ///
+    /// If no registered `AwsEvent` type recognises the payload,
+    /// [`LambdaHandler::fallback`] is invoked with the raw payload if one was
+    /// registered, and the future otherwise resolves to
+    /// [`RouterError::ParseFailed`]. If a parsed record's name/pattern has no
+    /// registered handler, the fallback is invoked with that record alone,
+    /// re-serialized as a one-record event; without a fallback the record
+    /// counts as failed, so the future resolves to
+    /// [`RouterError::NoRouteFound`] for event types without
+    /// `batch_item_ids`, and the record is reported in
+    /// [`BatchResponse::PartialFailures`] for event types with them.
+    fn call(&mut self, req: LambdaEvent<Value>) -> Self::Future {
+        let parsed = self.probes.iter().find_map(|probe| probe(&req.payload));
+
+        let Some(parsed) = parsed else {
+            return match &self.fallback {
+                Some(fallback) => {
+                    let future = fallback(req);
+                    Box::pin(async move {
+                        future
+                            .await
+                            .map(BatchResponse::Single)
+                            .map_err(RouterError::HandlerError)
+                    })
+                }
+                None => Box::pin(async { Err(RouterError::ParseFailed) }),
+            };
+        };
+
+        let batch_item_ids = parsed.batch_item_ids();
+        let ordered = parsed.ordered_batch();
+        let futures = parsed.dispatch(&*self, &req.context);
+
+        match batch_item_ids {
+            Some(item_ids) if ordered => Box::pin(dispatch_ordered_batch(futures, item_ids)),
+            Some(item_ids) => Box::pin(dispatch_unordered_batch(futures, item_ids)),
+            None => Box::pin(async move {
+                match reduce_batch(futures).await {
+                    Ok(response) => Ok(BatchResponse::Single(response)),
+                    Err(err) => Err(classify_dispatch_error(err)),
+                }
+            }),
+        }
+    }
+}
+
+/// Dispatches an unordered batch (`SqsEvent`): every record runs
+/// concurrently, and every record whose future errors is reported as its
+/// own [`BatchItemFailure`].
+async fn dispatch_unordered_batch<R>(
+    futures: Vec<LambdaFuture<FunctionResponse<R>>>,
+    item_ids: Vec<String>,
+) -> Result<BatchResponse<FunctionResponse<R>>, RouterError>
+where
+    R: Debug + Serialize + Send,
+{
+    let mut failures = Vec::new();
+    let mut last_ok = None;
+    for (result, item_id) in join_all(futures).await.into_iter().zip(item_ids) {
+        match result {
+            Ok(response) => last_ok = Some(response),
+            Err(_) => failures.push(BatchItemFailure {
+                item_identifier: item_id,
+            }),
+        }
     }
 
-    // Attempt to parse as SNS event
-    if let Ok(parsed_event) = SnsEvent::from_request(&event.payload) {
-        return Ok((TypeId::of::<SnsEvent>(), parsed_event.event_name()));
+    if failures.is_empty() {
+        last_ok
+            .map(BatchResponse::Single)
+            .ok_or(RouterError::NoRouteFound)
+    } else {
+        Ok(BatchResponse::PartialFailures(SqsBatchResponse {
+            batch_item_failures: failures,
+        }))
     }
+}
 
-    // Attempt to parse as SQS event
-    if let Ok(parsed_event) = SqsEvent::from_request(&event.payload) {
-        return Ok((TypeId::of::<SqsEvent>(), parsed_event.event_name()));
+/// Dispatches an ordered batch (`DynamoDbEvent`, `KinesisEvent`): records run
+/// one at a time, in order, stopping at the first failure and reporting only
+/// that record's id, since the redrive will re-deliver everything from that
+/// point on regardless of how later records in this invocation fared.
+async fn dispatch_ordered_batch<R>(
+    futures: Vec<LambdaFuture<FunctionResponse<R>>>,
+    item_ids: Vec<String>,
+) -> Result<BatchResponse<FunctionResponse<R>>, RouterError>
+where
+    R: Debug + Serialize + Send,
+{
+    let mut last_ok = None;
+    for (future, item_id) in futures.into_iter().zip(item_ids) {
+        match future.await {
+            Ok(response) => last_ok = Some(response),
+            Err(_) => {
+                return Ok(BatchResponse::PartialFailures(SqsBatchResponse {
+                    batch_item_failures: vec![BatchItemFailure {
+                        item_identifier: item_id,
+                    }],
+                }));
+            }
+        }
     }
 
-    Err("Failed to parse event".into())
+    last_ok
+        .map(BatchResponse::Single)
+        .ok_or(RouterError::NoRouteFound)
 }
 
+/// The whole router, wrapped up as a single boxed [`Service`] so that
+/// [`LambdaHandler::layer`] can wrap it in further layers without the
+/// resulting type depending on every layer that's been applied.
+type OuterService<R> = BoxService<LambdaEvent<Value>, BatchResponse<FunctionResponse<R>>, RouterError>;
+
 /// Router for AWS Lambda functions.
 ///
 /// Routes incoming AWS events to their corresponding handlers based
 /// on their types and names.
 pub struct LambdaHandler<R>
 where
-    R: Debug + Serialize + 'static,
+    R: Debug + Serialize + Send + 'static,
 {
-    // Mapping of event type and name to its handler
-    handlers: HashMap<(TypeId, String), Box<dyn Callable<R>>>,
+    core: RouterCore<R>,
+    // Set once `layer` has been called: the router wrapped in every layer
+    // applied so far, in application order. `core` is emptied into the
+    // innermost layer the first time this is built, so routes should be
+    // registered before layering.
+    outer: Option<OuterService<R>>,
 }
 
-impl<R: Debug + Serialize> LambdaHandler<R> {
+impl<R: Debug + Serialize + Send> LambdaHandler<R> {
     /// Creates a new `LambdaHandler`.
     ///
     /// # Returns
@@ -65,12 +502,18 @@ impl<R: Debug + Serialize> LambdaHandler<R> {
     /// A new `LambdaHandler` instance.
     pub fn new() -> Self {
         LambdaHandler {
-            handlers: HashMap::new(),
+            core: RouterCore::new(),
+            outer: None,
         }
     }
 
     /// Adds a route to the router.
     ///
+    /// The handler may resolve to anything that implements
+    /// [`IntoFunctionResponse`]: a plain serializable value is buffered as
+    /// usual, while a [`crate::response::StreamingResponse`] is streamed
+    /// back through [`LambdaHandler::run_streaming`].
+    ///
     /// # Arguments
     ///
     /// * `event_name` - The name of the AWS event to route.
@@ -79,25 +522,250 @@ impl<R: Debug + Serialize> LambdaHandler<R> {
     /// # Returns
     ///
     /// The router itself, allowing for method chaining.
-    pub fn route<E, F, Fut>(mut self, event_name: &str, handler: F) -> Self
+    pub fn route<E, F, Fut, O>(mut self, event_name: &str, handler: F) -> Self
     where
         E: AwsEvent + 'static,
         F: Fn(LambdaEvent<E>) -> Fut + Send + Sync + 'static,
-        Fut: Future<Output = Result<R, LambdaError>> + Send + 'static,
+        Fut: Future<Output = Result<O, LambdaError>> + Send + 'static,
+        O: IntoFunctionResponse<R> + 'static,
     {
         let boxed_event_handler = Box::new(AwsEventHandler::new(Box::new(move |event| {
-            Box::pin(handler(event)) as LambdaFuture<R>
+            let result = handler(event);
+            Box::pin(async move { result.await.map(IntoFunctionResponse::into_function_response) })
+                as LambdaFuture<FunctionResponse<R>>
         })));
 
-        self.handlers.insert(
+        self.core.ensure_probed::<E>();
+        self.core.handlers.insert(
             (TypeId::of::<E>(), event_name.to_string()),
             boxed_event_handler,
         );
         self
     }
+
+    /// Adds a pattern-matched route to the router, for cases where every
+    /// concrete event name can't be registered up front (e.g. an ARN or S3
+    /// key that varies per environment).
+    ///
+    /// Pattern routes are consulted, in the order they were registered,
+    /// only once the exact-match `route` table misses, and are matched
+    /// against the event's [`AwsEvent::match_key`] rather than its name.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - A regular expression evaluated against the event's match key.
+    /// * `handler` - The function to handle the routed event.
+    ///
+    /// # Returns
+    ///
+    /// The router itself, allowing for method chaining.
+    pub fn route_matching<E, F, Fut, O>(mut self, pattern: &str, handler: F) -> Self
+    where
+        E: AwsEvent + 'static,
+        F: Fn(LambdaEvent<E>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<O, LambdaError>> + Send + 'static,
+        O: IntoFunctionResponse<R> + 'static,
+    {
+        let regex = Regex::new(pattern).expect("invalid route_matching pattern");
+        let boxed_event_handler = Box::new(AwsEventHandler::new(Box::new(move |event| {
+            let result = handler(event);
+            Box::pin(async move { result.await.map(IntoFunctionResponse::into_function_response) })
+                as LambdaFuture<FunctionResponse<R>>
+        })));
+
+        self.core.ensure_probed::<E>();
+        self.core
+            .pattern_handlers
+            .push((TypeId::of::<E>(), regex, boxed_event_handler));
+        self
+    }
+
+    /// Registers a catch-all invoked whenever no route matches: either the
+    /// payload didn't parse as any registered `AwsEvent` type, or it did but
+    /// no `route`/`route_matching` matched its name or pattern. The handler
+    /// receives untyped JSON, so it can log, dead-letter, or best-effort
+    /// handle a shape the router wasn't built to recognise: the raw payload
+    /// when it didn't parse, or, when it parsed but a record had no route,
+    /// that record re-serialized as a one-record event (once per unmatched
+    /// record of a batch).
+    ///
+    /// Without a fallback, an unparseable payload resolves the invocation to
+    /// [`RouterError::ParseFailed`], and an unmatched record to
+    /// [`RouterError::NoRouteFound`] - or, for event types with
+    /// [`AwsEvent::batch_item_ids`], to an entry in
+    /// [`BatchResponse::PartialFailures`].
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The function to handle an otherwise-unroutable event.
+    ///
+    /// # Returns
+    ///
+    /// The router itself, allowing for method chaining.
+    pub fn fallback<F, Fut, O>(mut self, handler: F) -> Self
+    where
+        F: Fn(LambdaEvent<Value>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<O, LambdaError>> + Send + 'static,
+        O: IntoFunctionResponse<R> + 'static,
+    {
+        self.core.fallback = Some(Box::new(move |event| {
+            let result = handler(event);
+            Box::pin(async move { result.await.map(IntoFunctionResponse::into_function_response) })
+                as LambdaFuture<FunctionResponse<R>>
+        }));
+        self
+    }
+
+    /// Adds a route wrapped in a [`tower::Layer`], for cross-cutting
+    /// concerns - logging, timing, panic catching, retry - that should only
+    /// apply to one route rather than the whole router (see
+    /// [`LambdaHandler::layer`] for the whole-router equivalent).
+    ///
+    /// [`crate::layers::TracingLayer`] and [`crate::layers::CatchPanicLayer`]
+    /// are built-in layers suitable for this.
+    ///
+    /// # Arguments
+    ///
+    /// * `event_name` - The name of the AWS event to route.
+    /// * `handler` - The function to handle the routed event.
+    /// * `layer` - The layer to wrap the handler in.
+    ///
+    /// # Returns
+    ///
+    /// The router itself, allowing for method chaining.
+    pub fn route_layered<E, F, Fut, O, L>(mut self, event_name: &str, handler: F, layer: L) -> Self
+    where
+        E: AwsEvent + 'static,
+        F: Fn(LambdaEvent<E>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<O, LambdaError>> + Send + 'static,
+        O: IntoFunctionResponse<R> + 'static,
+        L: Layer<CallableService<FunctionResponse<R>>>,
+        L::Service: Service<LambdaEvent<Value>, Response = FunctionResponse<R>, Error = LambdaError>
+            + Send
+            + 'static,
+        <L::Service as Service<LambdaEvent<Value>>>::Future: Send + 'static,
+    {
+        let boxed_event_handler: Box<dyn Callable<FunctionResponse<R>>> =
+            Box::new(AwsEventHandler::new(Box::new(move |event| {
+                let result = handler(event);
+                Box::pin(
+                    async move { result.await.map(IntoFunctionResponse::into_function_response) },
+                ) as LambdaFuture<FunctionResponse<R>>
+            })));
+
+        let layered = layer.layer(CallableService {
+            callable: boxed_event_handler,
+        });
+        let layered_callable: Box<dyn Callable<FunctionResponse<R>> + Send> =
+            Box::new(LayeredCallable {
+                inner: Arc::new(Mutex::new(layered)),
+            });
+
+        self.core.ensure_probed::<E>();
+        self.core
+            .handlers
+            .insert((TypeId::of::<E>(), event_name.to_string()), layered_callable);
+        self
+    }
+
+    /// Wraps the whole router in a [`tower::Layer`], for cross-cutting
+    /// concerns that should see every event regardless of which route it
+    /// resolves to - structured logging, timing, panic catching, retry -
+    /// composed the same way a [`tower::ServiceBuilder`] stack would be.
+    ///
+    /// Register all routes before calling `layer`: the router's current
+    /// routes are captured into the innermost layer the first time this is
+    /// called, so a `route`/`route_matching` call afterwards would not be
+    /// seen by the layers already applied. Calling `layer` again stacks the
+    /// new layer around the previous one.
+    ///
+    /// # Arguments
+    ///
+    /// * `layer` - The layer to wrap the router in.
+    ///
+    /// # Returns
+    ///
+    /// The router itself, allowing for method chaining.
+    pub fn layer<L>(mut self, layer: L) -> Self
+    where
+        L: Layer<OuterService<R>>,
+        L::Service: Service<
+                LambdaEvent<Value>,
+                Response = BatchResponse<FunctionResponse<R>>,
+                Error = RouterError,
+            > + Send
+            + 'static,
+        <L::Service as Service<LambdaEvent<Value>>>::Future: Send + 'static,
+    {
+        let inner = self
+            .outer
+            .take()
+            .unwrap_or_else(|| BoxService::new(std::mem::replace(&mut self.core, RouterCore::new())));
+        self.outer = Some(BoxService::new(layer.layer(inner)));
+        self
+    }
+
+    /// Runs this router through `lambda_runtime`'s streaming response
+    /// entrypoint instead of [`Service::call`]'s plain JSON-serializing one.
+    ///
+    /// This is how a route's [`FunctionResponse::Streaming`] actually
+    /// reaches a Lambda Function URL in `RESPONSE_STREAM` mode: the chunks
+    /// are forwarded to the client as they're produced rather than buffered
+    /// into one JSON value first. Resolves each record to `lambda_runtime`'s
+    /// own [`lambda_runtime::types::FunctionResponse`] rather than
+    /// hand-rolling a response type, since that's what `lambda_runtime::run`
+    /// already knows how to dispatch as buffered or streaming.
+    ///
+    /// Only ever routes a single record per invocation - streaming targets
+    /// Function URLs, which never deliver a batch - so `SqsEvent` partial
+    /// batch failure reporting does not apply here. Does not go through
+    /// layers applied via [`LambdaHandler::layer`], since those wrap the
+    /// `BatchResponse`-returning `Service` impl. `layer` also moves the
+    /// routes and the fallback into that layered service, so a router that
+    /// has been layered has none left here and fails every event.
+    pub async fn run_streaming(self) -> Result<(), LambdaError>
+    where
+        R: 'static,
+    {
+        use lambda_runtime::streaming::{Body, StreamResponse};
+        use lambda_runtime::types::FunctionResponse as LambdaFunctionResponse;
+
+        let core = self.core;
+
+        lambda_runtime::run(lambda_runtime::service_fn(move |event: LambdaEvent<Value>| {
+            let future = core
+                .probes
+                .iter()
+                .find_map(|probe| probe(&event.payload))
+                .and_then(|parsed| parsed.dispatch(&core, &event.context).into_iter().next());
+
+            async move {
+                let response = match future {
+                    Some(future) => future.await?,
+                    None => match &core.fallback {
+                        Some(fallback) => fallback(event).await?,
+                        None => {
+                            return Err(LambdaError::from("I don't have a handler for this event!"))
+                        }
+                    },
+                };
+
+                Ok::<_, LambdaError>(match response {
+                    FunctionResponse::Buffered(value) => LambdaFunctionResponse::BufferedResponse(value),
+                    FunctionResponse::Streaming(stream) => {
+                        LambdaFunctionResponse::StreamingResponse(StreamResponse {
+                            metadata_prelude: Default::default(),
+                            stream: Body::from_stream(stream),
+                        })
+                    }
+                })
+            }
+        }))
+        .await
+    }
 }
 
-impl<R: Debug + Serialize> Default for LambdaHandler<R> {
+impl<R: Debug + Serialize + Send> Default for LambdaHandler<R> {
     /// Provides default instance of `LambdaHandler`.
     ///
     /// # Returns
@@ -108,17 +776,23 @@ impl<R: Debug + Serialize> Default for LambdaHandler<R> {
     }
 }
 
-impl<R: Debug + Serialize> Service<LambdaEvent<Value>> for LambdaHandler<R> {
-    type Response = R;
-    type Error = LambdaError;
-    type Future = LambdaFuture<R>;
+impl<R: Debug + Serialize + Send> Service<LambdaEvent<Value>> for LambdaHandler<R> {
+    type Response = BatchResponse<FunctionResponse<R>>;
+    type Error = RouterError;
+    type Future = RouterFuture<BatchResponse<FunctionResponse<R>>>;
 
     /// Checks if the service is ready to process a request.
-    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        Poll::Ready(Ok(()))
+    fn poll_ready(&mut self, cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        match &mut self.outer {
+            Some(outer) => outer.poll_ready(cx),
+            None => self.core.poll_ready(cx),
+        }
     }
 
-    /// Processes an incoming event, routing it to the appropriate handler.
+    /// Processes an incoming event, routing each of its records to the
+    /// appropriate handler independently and aggregating the results, then
+    /// running the result through any layers applied via
+    /// [`LambdaHandler::layer`].
     ///
     /// # Arguments
     ///
@@ -126,24 +800,227 @@ impl<R: Debug + Serialize> Service<LambdaEvent<Value>> for LambdaHandler<R> {
     ///
     /// # Returns
     ///
-    /// A future resolving to the response of the handler, or a Lambda error.
+    /// A future resolving to the aggregated response, or a [`RouterError`].
     fn call(&mut self, req: LambdaEvent<Value>) -> Self::Future {
-        let cloned_request = req.clone();
-        let event_key = get_event_key(cloned_request);
+        match &mut self.outer {
+            Some(outer) => outer.call(req),
+            None => self.core.call(req),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use serde_json::json;
+
+    use crate::events::{DynamoDbEvent, KinesisEvent, SqsEvent};
 
-        match event_key {
-            Ok(key) => {
-                if let Some(handler) = self.handlers.get(&key) {
-                    handler.call(req)
+    use super::*;
+
+    /// A batch with one failing record reports only that record's id, since
+    /// SQS records are independent of one another: the records that
+    /// succeeded are left out of the failure list and are not redriven.
+    #[tokio::test]
+    async fn sqs_batch_reports_only_the_failed_record() {
+        let handler = LambdaHandler::<String>::new().route(
+            "arn:aws:sqs:us-east-2:123456789012:my-queue",
+            |event: LambdaEvent<SqsEvent>| async move {
+                let message_id = event.payload.records[0].message_id.clone().unwrap_or_default();
+                if message_id == "msg-2" {
+                    Err(LambdaError::from("boom"))
                 } else {
-                    Box::pin(async {
-                        Err(LambdaError::from("I don't have a handler for this event!"))
-                    })
+                    Ok(message_id)
                 }
+            },
+        );
+
+        let payload = json!({
+            "Records": [
+                {
+                    "messageId": "msg-1",
+                    "body": "first",
+                    "eventSource": "aws:sqs",
+                    "eventSourceARN": "arn:aws:sqs:us-east-2:123456789012:my-queue",
+                    "awsRegion": "us-east-2",
+                },
+                {
+                    "messageId": "msg-2",
+                    "body": "second",
+                    "eventSource": "aws:sqs",
+                    "eventSourceARN": "arn:aws:sqs:us-east-2:123456789012:my-queue",
+                    "awsRegion": "us-east-2",
+                },
+                {
+                    "messageId": "msg-3",
+                    "body": "third",
+                    "eventSource": "aws:sqs",
+                    "eventSourceARN": "arn:aws:sqs:us-east-2:123456789012:my-queue",
+                    "awsRegion": "us-east-2",
+                },
+            ],
+        });
+
+        let response = handler
+            .oneshot(LambdaEvent {
+                payload,
+                context: Context::default(),
+            })
+            .await
+            .expect("invocation should not fail outright");
+
+        match response {
+            BatchResponse::PartialFailures(failures) => {
+                let ids: Vec<String> = failures
+                    .batch_item_failures
+                    .into_iter()
+                    .map(|failure| failure.item_identifier)
+                    .collect();
+                assert_eq!(ids, vec!["msg-2".to_string()]);
             }
-            Err(_) => {
-                Box::pin(async { Err(LambdaError::from("Unable to get event type or name!")) })
+            BatchResponse::Single(_) => panic!("expected a partial failure response"),
+        }
+    }
+
+    /// A DynamoDB Streams batch with a failing record is dispatched
+    /// sequentially, stops at the first failure, and reports only that
+    /// record's sequence number rather than one entry per failed record.
+    #[tokio::test]
+    async fn dynamodb_batch_stops_at_first_failure_and_reports_one_id() {
+        let dispatched = Arc::new(AtomicUsize::new(0));
+        let dispatched_in_handler = Arc::clone(&dispatched);
+
+        let handler = LambdaHandler::<String>::new().route(
+            "arn:aws:dynamodb:us-east-1:123456789012:table/my-table/stream/2024-01-01T00:00:00.000",
+            move |event: LambdaEvent<DynamoDbEvent>| {
+                let dispatched = Arc::clone(&dispatched_in_handler);
+                async move {
+                    dispatched.fetch_add(1, Ordering::SeqCst);
+                    let sequence_number = event.payload.records[0]
+                        .change
+                        .sequence_number
+                        .clone()
+                        .unwrap_or_default();
+                    if sequence_number == "seq-2" {
+                        Err(LambdaError::from("boom"))
+                    } else {
+                        Ok(sequence_number)
+                    }
+                }
+            },
+        );
+
+        let record = |sequence_number: &str| {
+            json!({
+                "eventID": sequence_number,
+                "eventName": "INSERT",
+                "eventSource": "aws:dynamodb",
+                "awsRegion": "us-east-1",
+                "dynamodb": {
+                    "SequenceNumber": sequence_number,
+                    "SizeBytes": 1,
+                    "StreamViewType": "NEW_IMAGE",
+                },
+                "eventSourceARN": "arn:aws:dynamodb:us-east-1:123456789012:table/my-table/stream/2024-01-01T00:00:00.000",
+            })
+        };
+        let payload = json!({
+            "Records": [record("seq-1"), record("seq-2"), record("seq-3")],
+        });
+
+        let response = handler
+            .oneshot(LambdaEvent {
+                payload,
+                context: Context::default(),
+            })
+            .await
+            .expect("invocation should not fail outright");
+
+        match response {
+            BatchResponse::PartialFailures(failures) => {
+                let ids: Vec<String> = failures
+                    .batch_item_failures
+                    .into_iter()
+                    .map(|failure| failure.item_identifier)
+                    .collect();
+                assert_eq!(ids, vec!["seq-2".to_string()]);
+            }
+            BatchResponse::Single(_) => panic!("expected a partial failure response"),
+        }
+        // The third record must never have been dispatched: once "seq-2"
+        // fails, the whole batch from that point on is redriven regardless.
+        assert_eq!(dispatched.load(Ordering::SeqCst), 2);
+    }
+
+    /// A Kinesis batch behaves the same way: sequential dispatch, stopping
+    /// at - and reporting only - the earliest failed sequence number.
+    #[tokio::test]
+    async fn kinesis_batch_stops_at_first_failure_and_reports_one_id() {
+        let dispatched = Arc::new(AtomicUsize::new(0));
+        let dispatched_in_handler = Arc::clone(&dispatched);
+
+        let handler = LambdaHandler::<String>::new().route(
+            "arn:aws:kinesis:us-east-1:123456789012:stream/my-stream",
+            move |event: LambdaEvent<KinesisEvent>| {
+                let dispatched = Arc::clone(&dispatched_in_handler);
+                async move {
+                    dispatched.fetch_add(1, Ordering::SeqCst);
+                    let sequence_number = event.payload.records[0]
+                        .kinesis
+                        .sequence_number
+                        .clone()
+                        .unwrap_or_default();
+                    if sequence_number == "seq-1" {
+                        Err(LambdaError::from("boom"))
+                    } else {
+                        Ok(sequence_number)
+                    }
+                }
+            },
+        );
+
+        let record = |sequence_number: &str| {
+            json!({
+                "eventID": format!("shardId-000000000000:{sequence_number}"),
+                "eventName": "aws:kinesis:record",
+                "eventSource": "aws:kinesis",
+                "awsRegion": "us-east-1",
+                "kinesis": {
+                    "kinesisSchemaVersion": "1.0",
+                    "partitionKey": "partition-1",
+                    "sequenceNumber": sequence_number,
+                    "data": "SGVsbG8=",
+                    "approximateArrivalTimestamp": 1_428_537_600,
+                },
+                "eventSourceARN": "arn:aws:kinesis:us-east-1:123456789012:stream/my-stream",
+            })
+        };
+        let payload = json!({
+            "Records": [record("seq-1"), record("seq-2")],
+        });
+
+        let response = handler
+            .oneshot(LambdaEvent {
+                payload,
+                context: Context::default(),
+            })
+            .await
+            .expect("invocation should not fail outright");
+
+        match response {
+            BatchResponse::PartialFailures(failures) => {
+                let ids: Vec<String> = failures
+                    .batch_item_failures
+                    .into_iter()
+                    .map(|failure| failure.item_identifier)
+                    .collect();
+                assert_eq!(ids, vec!["seq-1".to_string()]);
             }
+            BatchResponse::Single(_) => panic!("expected a partial failure response"),
         }
+        // The second record must never have been dispatched: the batch
+        // fails on the very first record.
+        assert_eq!(dispatched.load(Ordering::SeqCst), 1);
     }
 }
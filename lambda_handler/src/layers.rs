@@ -0,0 +1,181 @@
+use std::any::TypeId;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::task::{Context as TaskContext, Poll};
+use std::time::Instant;
+
+use futures::FutureExt;
+use lambda_runtime::{Error as LambdaError, LambdaEvent};
+use serde_json::Value;
+use tower::{Layer, Service};
+
+use crate::events::AwsEvent;
+
+/// [`Layer`] that logs the `(TypeId, event_name)` key a route resolved to,
+/// the handler's latency, and any `Err` it returned.
+///
+/// `E` pins the layer to the event type of the route it's applied to, so use
+/// this with [`crate::handler::LambdaHandler::route_layered`] (per-route),
+/// not [`crate::handler::LambdaHandler::layer`] (whole-router): a router
+/// typically has routes for more than one event type, and
+/// [`TracingService::call`] re-parses the raw payload as the single fixed
+/// `E` baked into the layer to recover the key to log, which is wrong for
+/// every invocation whose event isn't exactly `E`. Generic over the wrapped
+/// service's error type only so it composes with whichever error type a
+/// single route's service stack ends up using, not because it's meant to
+/// wrap the whole router.
+pub struct TracingLayer<E> {
+    _event: PhantomData<fn() -> E>,
+}
+
+impl<E> TracingLayer<E> {
+    /// Constructs a new `TracingLayer` for routes handling events of type `E`.
+    pub fn new() -> Self {
+        TracingLayer {
+            _event: PhantomData,
+        }
+    }
+}
+
+impl<E> Default for TracingLayer<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E> Clone for TracingLayer<E> {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+impl<E, S> Layer<S> for TracingLayer<E> {
+    type Service = TracingService<E, S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TracingService {
+            inner,
+            _event: PhantomData,
+        }
+    }
+}
+
+/// Service produced by [`TracingLayer`]. See its docs for what gets logged.
+pub struct TracingService<E, S> {
+    inner: S,
+    _event: PhantomData<fn() -> E>,
+}
+
+impl<E, S> Service<LambdaEvent<Value>> for TracingService<E, S>
+where
+    E: AwsEvent + 'static,
+    S: Service<LambdaEvent<Value>>,
+    S::Future: Send + 'static,
+    S::Response: Send + 'static,
+    S::Error: std::fmt::Display + Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: LambdaEvent<Value>) -> Self::Future {
+        let type_id = TypeId::of::<E>();
+        let event_name = E::from_request(&req.payload)
+            .map(|event| event.event_name())
+            .unwrap_or_else(|_| "<unparseable>".to_string());
+        let start = Instant::now();
+        let fut = self.inner.call(req);
+
+        Box::pin(async move {
+            let result = fut.await;
+            let elapsed_ms = start.elapsed().as_millis() as u64;
+            match &result {
+                Ok(_) => tracing::info!(?type_id, %event_name, elapsed_ms, "handled event"),
+                Err(err) => {
+                    tracing::error!(?type_id, %event_name, elapsed_ms, %err, "handler failed")
+                }
+            }
+            result
+        })
+    }
+}
+
+/// [`Layer`] that catches a panic inside the wrapped service and turns it
+/// into a [`LambdaError`], so one malformed event can't take the whole
+/// runtime down with it. Fixed to [`LambdaError`] rather than generic over
+/// the wrapped service's error, so use this with
+/// [`crate::handler::LambdaHandler::route_layered`] (per-handler), not
+/// [`crate::handler::LambdaHandler::layer`].
+#[derive(Clone, Default)]
+pub struct CatchPanicLayer;
+
+impl CatchPanicLayer {
+    /// Constructs a new `CatchPanicLayer`.
+    pub fn new() -> Self {
+        CatchPanicLayer
+    }
+}
+
+impl<S> Layer<S> for CatchPanicLayer {
+    type Service = CatchPanicService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CatchPanicService { inner }
+    }
+}
+
+/// Service produced by [`CatchPanicLayer`]. See its docs for what it does.
+pub struct CatchPanicService<S> {
+    inner: S,
+}
+
+impl<S, Req> Service<Req> for CatchPanicService<S>
+where
+    S: Service<Req, Error = LambdaError>,
+    S::Future: Send + 'static,
+    S::Response: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = LambdaError;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, LambdaError>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Req) -> Self::Future {
+        let fut = match std::panic::catch_unwind(AssertUnwindSafe(|| self.inner.call(req))) {
+            Ok(fut) => fut,
+            Err(panic) => {
+                let err = LambdaError::from(format!("handler panicked: {}", panic_message(&*panic)));
+                return Box::pin(async move { Err(err) });
+            }
+        };
+        Box::pin(async move {
+            match AssertUnwindSafe(fut).catch_unwind().await {
+                Ok(result) => result,
+                Err(panic) => Err(LambdaError::from(format!(
+                    "handler panicked: {}",
+                    panic_message(&*panic)
+                ))),
+            }
+        })
+    }
+}
+
+/// Extracts a human-readable message from a caught panic payload.
+fn panic_message(panic: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
@@ -0,0 +1,79 @@
+use std::fmt::{self, Debug};
+
+use bytes::Bytes;
+use futures::stream::Stream;
+use lambda_runtime::Error as LambdaError;
+use serde::{Serialize, Serializer};
+
+/// A boxed byte stream, used as the common representation for a streaming
+/// handler response regardless of the concrete `Stream` type it started as.
+pub type BoxByteStream = std::pin::Pin<Box<dyn Stream<Item = Result<Bytes, LambdaError>> + Send>>;
+
+/// A handler's resolved response: either a single buffered value, returned
+/// for a normal invocation, or a byte stream, returned to a Lambda Function
+/// URL running in `RESPONSE_STREAM` mode so large payloads don't have to be
+/// buffered in memory before they're sent.
+pub enum FunctionResponse<R> {
+    Buffered(R),
+    Streaming(BoxByteStream),
+}
+
+impl<R: Debug> Debug for FunctionResponse<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FunctionResponse::Buffered(response) => {
+                f.debug_tuple("Buffered").field(response).finish()
+            }
+            FunctionResponse::Streaming(_) => f.debug_tuple("Streaming").field(&"<stream>").finish(),
+        }
+    }
+}
+
+impl<R: Serialize> Serialize for FunctionResponse<R> {
+    /// Serializes the buffered variant as `R`. A streaming response can't be
+    /// drained synchronously, so it refuses to serialize instead of
+    /// panicking or buffering the whole stream: handlers that return
+    /// `FunctionResponse::Streaming` must be run through
+    /// [`crate::handler::LambdaHandler::run_streaming`], not the plain
+    /// JSON-serializing invoke path.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            FunctionResponse::Buffered(response) => response.serialize(serializer),
+            FunctionResponse::Streaming(_) => Err(serde::ser::Error::custom(
+                "a streaming FunctionResponse cannot be JSON-serialized; invoke the handler \
+                 through LambdaHandler::run_streaming instead",
+            )),
+        }
+    }
+}
+
+/// Converts a handler's return value into a [`FunctionResponse`], so a
+/// route can resolve to either a plain serializable value or a byte stream
+/// without the router needing to know which.
+pub trait IntoFunctionResponse<R> {
+    fn into_function_response(self) -> FunctionResponse<R>;
+}
+
+impl<R: Serialize> IntoFunctionResponse<R> for R {
+    fn into_function_response(self) -> FunctionResponse<R> {
+        FunctionResponse::Buffered(self)
+    }
+}
+
+/// Wraps any compatible byte stream as a [`FunctionResponse::Streaming`].
+/// Route handlers that want to stream should return this wrapper around
+/// their stream rather than the stream itself, since a bare `R` is always
+/// read as the buffered case.
+pub struct StreamingResponse<S>(pub S);
+
+impl<R, S> IntoFunctionResponse<R> for StreamingResponse<S>
+where
+    S: Stream<Item = Result<Bytes, LambdaError>> + Send + 'static,
+{
+    fn into_function_response(self) -> FunctionResponse<R> {
+        FunctionResponse::Streaming(Box::pin(self.0))
+    }
+}